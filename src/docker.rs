@@ -0,0 +1,167 @@
+use std::collections::{HashMap, HashSet};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use bollard::container::{ListContainersOptions, PruneContainersOptions};
+use bollard::image::{ListImagesOptions, PruneImagesOptions, RemoveImageOptions};
+use bollard::models::{
+    ContainerPruneResponse, ContainerSummary, ImagePruneResponse, ImageSummary,
+    NetworkPruneResponse, SystemDataUsageResponse, VolumePruneResponse,
+};
+use bollard::network::PruneNetworksOptions;
+use bollard::volume::PruneVolumesOptions;
+use bollard::Docker;
+
+pub type DockerResult<T> = Result<T, bollard::errors::Error>;
+
+/// Connect to the Docker daemon named by `DOCKER_HOST` (a `unix://` socket or
+/// a `tcp://`/`http://` endpoint), falling back to the local unix socket (or
+/// named pipe on Windows) when it is unset. `DOCKER_TLS_VERIFY` and
+/// `DOCKER_CERT_PATH` are honored only when bollard is built with its `ssl`
+/// feature.
+pub fn connect() -> DockerResult<Docker> {
+    Docker::connect_with_defaults()
+}
+
+/// List every image on the daemon, tagged or not.
+pub async fn list_images(docker: &Docker) -> DockerResult<Vec<ImageSummary>> {
+    docker.list_images::<String>(None).await
+}
+
+/// List every container, including stopped ones.
+pub async fn list_containers(docker: &Docker) -> DockerResult<Vec<ContainerSummary>> {
+    let options = ListContainersOptions::<String> {
+        all: true,
+        ..Default::default()
+    };
+    docker.list_containers(Some(options)).await
+}
+
+/// Count images that aren't referenced by any tag.
+pub async fn count_dangling_images(docker: &Docker) -> DockerResult<usize> {
+    let mut filters = HashMap::new();
+    filters.insert("dangling".to_string(), vec!["true".to_string()]);
+
+    let options = ListImagesOptions::<String> {
+        filters,
+        ..Default::default()
+    };
+
+    Ok(docker.list_images(Some(options)).await?.len())
+}
+
+/// Remove all dangling images, returning the daemon's prune report.
+pub async fn remove_dangling_images(docker: &Docker) -> DockerResult<ImagePruneResponse> {
+    let mut filters = HashMap::new();
+    filters.insert("dangling".to_string(), vec!["true".to_string()]);
+
+    let options = PruneImagesOptions { filters };
+    docker.prune_images(Some(options)).await
+}
+
+/// Remove every stopped container.
+pub async fn remove_stopped_containers(docker: &Docker) -> DockerResult<ContainerPruneResponse> {
+    docker
+        .prune_containers(None::<PruneContainersOptions<String>>)
+        .await
+}
+
+/// Fetch the daemon's disk usage breakdown (images, containers, volumes,
+/// build cache), equivalent to `docker system df`.
+pub async fn disk_usage(docker: &Docker) -> DockerResult<SystemDataUsageResponse> {
+    docker.df().await
+}
+
+/// What `system_prune` removes: stopped containers, unused networks and
+/// dangling images. Unlike `docker system prune -f`, the build cache is not
+/// pruned. Volumes are left alone, matching `docker system prune` without
+/// `--volumes`.
+#[derive(Debug, Default)]
+pub struct SystemPruneReport {
+    pub containers: Option<ContainerPruneResponse>,
+    pub networks: Option<NetworkPruneResponse>,
+    pub images: Option<ImagePruneResponse>,
+}
+
+pub async fn system_prune(docker: &Docker) -> DockerResult<SystemPruneReport> {
+    let containers = docker
+        .prune_containers(None::<PruneContainersOptions<String>>)
+        .await?;
+    let networks = docker
+        .prune_networks(None::<PruneNetworksOptions<String>>)
+        .await?;
+
+    let mut filters = HashMap::new();
+    filters.insert("dangling".to_string(), vec!["true".to_string()]);
+    let images = docker
+        .prune_images(Some(PruneImagesOptions { filters }))
+        .await?;
+
+    Ok(SystemPruneReport {
+        containers: Some(containers),
+        networks: Some(networks),
+        images: Some(images),
+    })
+}
+
+/// Remove every unused volume, equivalent to `docker volume prune -f`.
+#[allow(dead_code)]
+pub async fn remove_unused_volumes(docker: &Docker) -> DockerResult<VolumePruneResponse> {
+    docker.prune_volumes(None::<PruneVolumesOptions<String>>).await
+}
+
+fn unix_now() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or(0)
+}
+
+/// Images created more than `days` ago. When `protect_running` is set,
+/// images backing a currently-running container are left out even if they're
+/// old. `exclude_repos` is a list of plain substrings matched against each
+/// image's repo:tag strings; an image with any matching tag is left out.
+pub async fn images_older_than(
+    docker: &Docker,
+    days: u64,
+    protect_running: bool,
+    exclude_repos: &[String],
+) -> DockerResult<Vec<ImageSummary>> {
+    let cutoff = unix_now() - days as i64 * 86_400;
+    let images = list_images(docker).await?;
+
+    let running_image_ids: HashSet<String> = if protect_running {
+        list_containers(docker)
+            .await?
+            .into_iter()
+            .filter(|c| c.state.as_deref() == Some("running"))
+            .filter_map(|c| c.image_id)
+            .collect()
+    } else {
+        HashSet::new()
+    };
+
+    Ok(images
+        .into_iter()
+        .filter(|img| img.created < cutoff)
+        .filter(|img| !running_image_ids.contains(&img.id))
+        .filter(|img| {
+            !img.repo_tags
+                .iter()
+                .any(|tag| exclude_repos.iter().any(|pattern| tag.contains(pattern.as_str())))
+        })
+        .collect())
+}
+
+/// Remove each of `images`, returning the per-image result so the caller can
+/// report partial failures without aborting the rest of the batch.
+pub async fn remove_images(docker: &Docker, images: &[ImageSummary]) -> Vec<(String, DockerResult<()>)> {
+    let mut results = Vec::with_capacity(images.len());
+    for image in images {
+        let result = docker
+            .remove_image(&image.id, None::<RemoveImageOptions>, None)
+            .await
+            .map(|_| ());
+        results.push((image.id.clone(), result));
+    }
+    results
+}
@@ -3,9 +3,100 @@ use std::fs::{self, File};
 use std::io::{self, Read};
 use std::collections::{HashMap, HashSet};
 use std::time::{SystemTime, Duration};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
 use walkdir::WalkDir;
 use sha2::{Sha256, Digest};
 use humansize::{format_size, BINARY};
+use rayon::prelude::*;
+use serde::{Serialize, Deserialize};
+use globset::{Glob, GlobSet, GlobSetBuilder};
+
+/// Number of bytes read from the front of a file for the partial-hash stage.
+const PARTIAL_HASH_SIZE: u64 = 1024 * 1024;
+
+/// Number of files processed per rayon work item, so the stop flag and
+/// progress counter are checked/updated at a reasonable granularity instead
+/// of on every single file.
+const PROGRESS_CHUNK_SIZE: usize = 256;
+
+/// Which phase of `analyze_disk` a `ProgressData` update refers to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScanStage {
+    LargeFiles,
+    Duplicates,
+    OldFiles,
+    CacheDirs,
+}
+
+impl ScanStage {
+    pub fn label(&self) -> &'static str {
+        match self {
+            ScanStage::LargeFiles => "large files",
+            ScanStage::Duplicates => "duplicates",
+            ScanStage::OldFiles => "old files",
+            ScanStage::CacheDirs => "cache directories",
+        }
+    }
+}
+
+/// A snapshot of scan progress, sent over a `ProgressHandle`'s channel so a
+/// caller (CLI or future GUI) can render a progress bar instead of the scan
+/// appearing to hang.
+#[derive(Debug, Clone)]
+pub struct ProgressData {
+    pub stage: ScanStage,
+    pub files_checked: usize,
+    pub files_to_check: usize,
+}
+
+/// Shared handle threaded through `analyze_disk` and its scan functions for
+/// live progress reporting and cooperative cancellation.
+///
+/// Cloning is cheap: the sender and stop flag are both reference-counted, so
+/// every rayon worker can hold its own handle.
+#[derive(Clone)]
+pub struct ProgressHandle {
+    sender: Option<crossbeam_channel::Sender<ProgressData>>,
+    stop: Arc<AtomicBool>,
+}
+
+impl ProgressHandle {
+    pub fn new(sender: crossbeam_channel::Sender<ProgressData>, stop: Arc<AtomicBool>) -> Self {
+        Self { sender: Some(sender), stop }
+    }
+
+    /// A handle that reports nothing and can never be stopped, for callers
+    /// that don't care about progress or cancellation.
+    pub fn none() -> Self {
+        Self { sender: None, stop: Arc::new(AtomicBool::new(false)) }
+    }
+
+    fn report(&self, stage: ScanStage, files_checked: usize, files_to_check: usize) {
+        if let Some(sender) = &self.sender {
+            let _ = sender.send(ProgressData { stage, files_checked, files_to_check });
+        }
+    }
+
+    /// Whether the caller has requested the scan stop early.
+    pub fn is_stopped(&self) -> bool {
+        self.stop.load(Ordering::Relaxed)
+    }
+}
+
+/// Hashing algorithm used when fingerprinting file contents.
+///
+/// `Xxh3` is the default: it's a fast non-cryptographic hash, which is all we
+/// need for dedup (we only care about collision-resistance, not security).
+/// `Sha256` is kept around for callers that want a cryptographic guarantee.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum HashAlgo {
+    Blake3,
+    #[default]
+    Xxh3,
+    Crc32,
+    Sha256,
+}
 
 #[derive(Debug, Clone)]
 #[allow(dead_code)]
@@ -14,6 +105,22 @@ pub struct FileInfo {
     pub size: u64,
     pub last_accessed: SystemTime,
     pub last_modified: SystemTime,
+    /// `(device, inode)` on Unix, used to detect hardlinks to the same
+    /// physical file. Always `None` on platforms without that concept.
+    pub dev_ino: Option<(u64, u64)>,
+}
+
+/// Read a file's `(device, inode)` pair on Unix so hardlinked duplicates can
+/// be recognized as a single physical file.
+#[cfg(unix)]
+fn dev_ino(metadata: &fs::Metadata) -> Option<(u64, u64)> {
+    use std::os::unix::fs::MetadataExt;
+    Some((metadata.dev(), metadata.ino()))
+}
+
+#[cfg(not(unix))]
+fn dev_ino(_metadata: &fs::Metadata) -> Option<(u64, u64)> {
+    None
 }
 
 #[derive(Debug, Clone)]
@@ -37,6 +144,22 @@ pub struct AnalysisConfig {
     pub min_file_size_mb: u64,
     pub old_file_days: u64,
     pub max_large_files: usize,
+    pub hash_algo: HashAlgo,
+    pub use_hash_cache: bool,
+    /// When non-empty, only files with one of these extensions are scanned.
+    pub allowed_extensions: Vec<String>,
+    /// Files with one of these extensions are never scanned, even if they
+    /// also match `allowed_extensions`.
+    pub excluded_extensions: Vec<String>,
+    /// Glob/wildcard patterns (e.g. `*/.git/*`, `*/node_modules/*`) matched
+    /// against the full path. Accepts both `/` and `\` separators.
+    pub excluded_items: Vec<String>,
+    /// Directories pruned entirely during traversal, so their contents are
+    /// never walked in the first place (e.g. `node_modules`, `.git`, or a
+    /// mount point the user wants left alone).
+    pub excluded_directories: Vec<PathBuf>,
+    /// Number of worker threads used for parallel scanning/hashing.
+    pub thread_count: usize,
 }
 
 impl Default for AnalysisConfig {
@@ -45,7 +168,93 @@ impl Default for AnalysisConfig {
             min_file_size_mb: 100,
             old_file_days: 180,
             max_large_files: 10,
+            hash_algo: HashAlgo::default(),
+            use_hash_cache: true,
+            thread_count: std::thread::available_parallelism().map(|n| n.get()).unwrap_or(1),
+            allowed_extensions: Vec::new(),
+            excluded_extensions: Vec::new(),
+            excluded_items: Vec::new(),
+            excluded_directories: Vec::new(),
+        }
+    }
+}
+
+/// Compiled form of `AnalysisConfig`'s extension and path-pattern filters,
+/// built once per scan and shared across all the `find_*` functions rather
+/// than re-parsed per file.
+pub struct PathFilter {
+    allowed_extensions: HashSet<String>,
+    excluded_extensions: HashSet<String>,
+    excluded_items: GlobSet,
+}
+
+impl PathFilter {
+    pub fn new(config: &AnalysisConfig) -> Self {
+        let mut builder = GlobSetBuilder::new();
+        for pattern in &config.excluded_items {
+            if let Ok(glob) = Glob::new(&normalize_for_match(pattern)) {
+                builder.add(glob);
+            }
+        }
+        // `excluded_directories` is just sugar for a pair of glob patterns on
+        // the same matcher: one for the directory itself (wherever it shows
+        // up in the tree), one for everything beneath it.
+        for dir in &config.excluded_directories {
+            let dir_str = normalize_for_match(&dir.to_string_lossy());
+            if let Ok(glob) = Glob::new(&format!("**/{}", dir_str)) {
+                builder.add(glob);
+            }
+            if let Ok(glob) = Glob::new(&format!("**/{}/**", dir_str)) {
+                builder.add(glob);
+            }
+        }
+
+        Self {
+            allowed_extensions: config.allowed_extensions.iter().map(|e| e.to_lowercase()).collect(),
+            excluded_extensions: config.excluded_extensions.iter().map(|e| e.to_lowercase()).collect(),
+            excluded_items: builder.build().unwrap_or_else(|_| GlobSetBuilder::new().build().unwrap()),
+        }
+    }
+
+    /// Whether `path` should be skipped. `is_dir` suppresses the extension
+    /// checks for directories, which don't have a meaningful extension.
+    pub fn is_excluded(&self, path: &Path, is_dir: bool) -> bool {
+        if should_exclude_path(path) {
+            return true;
+        }
+
+        if self.excluded_items.is_match(normalize_for_match(&path.to_string_lossy())) {
+            return true;
         }
+
+        if is_dir {
+            return false;
+        }
+
+        let ext = path
+            .extension()
+            .map(|e| e.to_string_lossy().to_lowercase())
+            .unwrap_or_default();
+
+        if !self.allowed_extensions.is_empty() && !self.allowed_extensions.contains(&ext) {
+            return true;
+        }
+
+        self.excluded_extensions.contains(&ext)
+    }
+}
+
+/// Normalize a path or pattern for matching: accept both `/` and `\` as
+/// separators, and ignore case on Windows where paths are case-insensitive.
+fn normalize_for_match(value: &str) -> String {
+    let slashed = value.replace('\\', "/");
+    #[cfg(windows)]
+    {
+        slashed.to_lowercase()
+    }
+    #[cfg(not(windows))]
+    {
+        slashed
     }
 }
 
@@ -69,151 +278,361 @@ fn should_exclude_path(path: &Path) -> bool {
 }
 
 /// Find large files above a certain size threshold
-pub fn find_large_files(root: &Path, min_size_mb: u64) -> Vec<FileInfo> {
+pub fn find_large_files(root: &Path, min_size_mb: u64, progress: &ProgressHandle, filter: &PathFilter) -> Vec<FileInfo> {
     let min_size_bytes = min_size_mb * 1024 * 1024;
-    let mut files = Vec::new();
-    
-    for entry in WalkDir::new(root)
+
+    let entries: Vec<PathBuf> = WalkDir::new(root)
         .follow_links(false)
         .into_iter()
-        .filter_entry(|e| !should_exclude_path(e.path()))
-    {
-        match entry {
-            Ok(entry) => {
-                if entry.file_type().is_file() {
-                    if let Ok(metadata) = entry.metadata() {
-                        let size = metadata.len();
-                        if size >= min_size_bytes {
-                            if let (Ok(accessed), Ok(modified)) = (
-                                metadata.accessed(),
-                                metadata.modified(),
-                            ) {
-                                files.push(FileInfo {
-                                    path: entry.path().to_path_buf(),
-                                    size,
-                                    last_accessed: accessed,
-                                    last_modified: modified,
-                                });
-                            }
-                        }
-                    }
-                }
+        .filter_entry(|e| !filter.is_excluded(e.path(), e.file_type().is_dir()))
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_file())
+        .map(|e| e.path().to_path_buf())
+        .collect();
+
+    let total = entries.len();
+    let checked = AtomicUsize::new(0);
+
+    let mut files: Vec<FileInfo> = entries
+        .par_chunks(PROGRESS_CHUNK_SIZE)
+        .flat_map(|chunk| {
+            if progress.is_stopped() {
+                return Vec::new();
             }
-            Err(_) => continue, // Skip permission errors
-        }
-    }
-    
+            let found: Vec<FileInfo> = chunk
+                .iter()
+                .filter_map(|path| {
+                    let metadata = fs::metadata(path).ok()?;
+                    let size = metadata.len();
+                    if size < min_size_bytes {
+                        return None;
+                    }
+                    let accessed = metadata.accessed().ok()?;
+                    let modified = metadata.modified().ok()?;
+                    Some(FileInfo {
+                        path: path.clone(),
+                        size,
+                        last_accessed: accessed,
+                        last_modified: modified,
+                        dev_ino: dev_ino(&metadata),
+                    })
+                })
+                .collect();
+
+            let done = checked.fetch_add(chunk.len(), Ordering::Relaxed) + chunk.len();
+            progress.report(ScanStage::LargeFiles, done, total);
+            found
+        })
+        .collect();
+
     // Sort by size descending
-    files.sort_by(|a, b| b.size.cmp(&a.size));
+    files.sort_by_key(|f| std::cmp::Reverse(f.size));
     files
 }
 
-/// Calculate SHA-256 hash of a file
-fn hash_file(path: &Path) -> io::Result<String> {
+/// Hash a file's contents using the given algorithm.
+///
+/// When `limit` is `Some(n)`, only the first `n` bytes are read and hashed;
+/// this backs the cheap partial-hash stage in `find_duplicates`. `None`
+/// hashes the whole file.
+fn hash_file(path: &Path, algo: HashAlgo, limit: Option<u64>) -> io::Result<String> {
     let mut file = File::open(path)?;
-    let mut hasher = Sha256::new();
     let mut buffer = [0u8; 8192];
-    
-    loop {
-        let bytes_read = file.read(&mut buffer)?;
-        if bytes_read == 0 {
-            break;
+    let mut remaining = limit.unwrap_or(u64::MAX);
+
+    macro_rules! digest_loop {
+        ($hasher:expr) => {{
+            loop {
+                if remaining == 0 {
+                    break;
+                }
+                let to_read = buffer.len().min(remaining as usize) as usize;
+                let bytes_read = file.read(&mut buffer[..to_read.max(1)])?;
+                if bytes_read == 0 {
+                    break;
+                }
+                $hasher.update(&buffer[..bytes_read]);
+                remaining = remaining.saturating_sub(bytes_read as u64);
+            }
+        }};
+    }
+
+    match algo {
+        HashAlgo::Sha256 => {
+            let mut hasher = Sha256::new();
+            digest_loop!(hasher);
+            Ok(format!("{:x}", hasher.finalize()))
+        }
+        HashAlgo::Blake3 => {
+            let mut hasher = blake3::Hasher::new();
+            digest_loop!(hasher);
+            Ok(hasher.finalize().to_hex().to_string())
+        }
+        HashAlgo::Xxh3 => {
+            let mut hasher = xxhash_rust::xxh3::Xxh3::new();
+            digest_loop!(hasher);
+            Ok(format!("{:x}", hasher.digest()))
+        }
+        HashAlgo::Crc32 => {
+            let mut hasher = crc32fast::Hasher::new();
+            digest_loop!(hasher);
+            Ok(format!("{:x}", hasher.finalize()))
         }
-        hasher.update(&buffer[..bytes_read]);
     }
-    
-    Ok(format!("{:x}", hasher.finalize()))
 }
 
-/// Find duplicate files using SHA-256 content hashing
-pub fn find_duplicates(root: &Path) -> Vec<Vec<FileInfo>> {
+/// A cached hash for a file, keyed by its path alongside the size/mtime/algo
+/// it was computed with, so a changed file — or a run using a different
+/// `HashAlgo` — is detected and re-hashed instead of trusting a stale value.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct CacheEntry {
+    size: u64,
+    modified: u64,
+    algo: HashAlgo,
+    hash: String,
+}
+
+type HashCache = HashMap<PathBuf, CacheEntry>;
+
+fn system_time_to_secs(time: SystemTime) -> u64 {
+    time.duration_since(SystemTime::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+fn cache_file_path() -> Option<PathBuf> {
+    directories::ProjectDirs::from("", "", "docker-cleanup")
+        .map(|dirs| dirs.cache_dir().join("hash_cache.json"))
+}
+
+/// Load the on-disk hash cache, pruning any entries whose path no longer
+/// exists. Returns an empty cache if none has been saved yet or it can't be
+/// read (corrupt, wrong format, etc.) rather than failing the whole scan.
+fn load_cache() -> HashCache {
+    let Some(path) = cache_file_path() else {
+        return HashMap::new();
+    };
+
+    let Ok(data) = fs::read_to_string(&path) else {
+        return HashMap::new();
+    };
+
+    let Ok(entries) = serde_json::from_str::<HashCache>(&data) else {
+        return HashMap::new();
+    };
+
+    entries.into_iter().filter(|(path, _)| path.exists()).collect()
+}
+
+/// Persist the hash cache to disk, creating its parent directory if needed.
+fn save_cache(cache: &HashCache) {
+    let Some(path) = cache_file_path() else {
+        return;
+    };
+
+    if let Some(parent) = path.parent() {
+        let _ = fs::create_dir_all(parent);
+    }
+
+    if let Ok(data) = serde_json::to_string(cache) {
+        let _ = fs::write(path, data);
+    }
+}
+
+/// Find duplicate files using a three-stage pipeline: group by size, then
+/// narrow each size group with a cheap partial hash (first `PARTIAL_HASH_SIZE`
+/// bytes), and only fully hash files that still collide after that. This
+/// avoids reading entire gigabyte-sized files that merely share a size.
+pub fn find_duplicates(root: &Path, config: &AnalysisConfig, progress: &ProgressHandle, filter: &PathFilter) -> Vec<Vec<FileInfo>> {
+    let cache = if config.use_hash_cache {
+        Some(std::sync::Mutex::new(load_cache()))
+    } else {
+        None
+    };
+
     let mut size_groups: HashMap<u64, Vec<FileInfo>> = HashMap::new();
-    
-    // First pass: group files by size
+
+    // Stage 1: group files by size
     for entry in WalkDir::new(root)
         .follow_links(false)
         .into_iter()
-        .filter_entry(|e| !should_exclude_path(e.path()))
+        .filter_entry(|e| !filter.is_excluded(e.path(), e.file_type().is_dir()))
+        .flatten()
     {
-        if let Ok(entry) = entry {
-            if entry.file_type().is_file() {
-                if let Ok(metadata) = entry.metadata() {
-                    let size = metadata.len();
-                    // Only consider files larger than 1KB to avoid noise
-                    if size > 1024 {
-                        if let (Ok(accessed), Ok(modified)) = (
-                            metadata.accessed(),
-                            metadata.modified(),
-                        ) {
-                            let file_info = FileInfo {
-                                path: entry.path().to_path_buf(),
-                                size,
-                                last_accessed: accessed,
-                                last_modified: modified,
-                            };
-                            size_groups.entry(size).or_insert_with(Vec::new).push(file_info);
-                        }
+        if entry.file_type().is_file() {
+            if let Ok(metadata) = entry.metadata() {
+                let size = metadata.len();
+                // Only consider files larger than 1KB to avoid noise
+                if size > 1024 {
+                    if let (Ok(accessed), Ok(modified)) = (
+                        metadata.accessed(),
+                        metadata.modified(),
+                    ) {
+                        let file_info = FileInfo {
+                            path: entry.path().to_path_buf(),
+                            size,
+                            last_accessed: accessed,
+                            last_modified: modified,
+                            dev_ino: dev_ino(&metadata),
+                        };
+                        size_groups.entry(size).or_default().push(file_info);
                     }
                 }
             }
         }
     }
-    
-    // Second pass: hash files with same size
-    let mut hash_groups: HashMap<String, Vec<FileInfo>> = HashMap::new();
-    
-    for (_, files) in size_groups {
-        if files.len() > 1 {
-            for file in files {
-                if let Ok(hash) = hash_file(&file.path) {
-                    hash_groups.entry(hash).or_insert_with(Vec::new).push(file);
-                }
-            }
+
+    // Stage 2: for each size group with more than one member, sub-group by a
+    // cheap partial hash over just the first PARTIAL_HASH_SIZE bytes.
+    let candidates: Vec<FileInfo> = size_groups
+        .into_values()
+        .filter(|files| files.len() > 1)
+        .flatten()
+        .collect();
+
+    let partial_groups = hash_into_groups(&candidates, config.hash_algo, Some(PARTIAL_HASH_SIZE), ScanStage::Duplicates, progress, None);
+
+    // Stage 3: only for partial-hash collisions, hash the entire file. Files
+    // no larger than PARTIAL_HASH_SIZE were already read in full during the
+    // partial-hash stage, so their partial hash IS their full-content hash —
+    // skip straight to treating those as confirmed duplicates.
+    let mut full_groups: HashMap<String, Vec<FileInfo>> = HashMap::new();
+    let mut still_colliding: Vec<FileInfo> = Vec::new();
+
+    for (hash, files) in partial_groups {
+        if files.len() <= 1 {
+            continue;
+        }
+        if files.iter().all(|f| f.size <= PARTIAL_HASH_SIZE) {
+            full_groups.insert(hash, files);
+        } else {
+            still_colliding.extend(files);
         }
     }
-    
+
+    // This is the expensive step, so it's the one backed by the on-disk cache.
+    full_groups.extend(hash_into_groups(&still_colliding, config.hash_algo, None, ScanStage::Duplicates, progress, cache.as_ref()));
+
+    if let Some(cache) = &cache {
+        save_cache(&cache.lock().unwrap());
+    }
+
     // Return only groups with duplicates
-    hash_groups
+    full_groups
         .into_iter()
         .filter(|(_, files)| files.len() > 1)
         .map(|(_, files)| files)
         .collect()
 }
 
+/// Hash `files` in parallel with rayon and bucket them by the resulting
+/// digest, reporting progress and honoring the stop flag between chunks. When
+/// `cache` is provided, a file whose path/size/mtime match a cached entry
+/// reuses that hash instead of re-reading the file.
+fn hash_into_groups(
+    files: &[FileInfo],
+    algo: HashAlgo,
+    limit: Option<u64>,
+    stage: ScanStage,
+    progress: &ProgressHandle,
+    cache: Option<&std::sync::Mutex<HashCache>>,
+) -> HashMap<String, Vec<FileInfo>> {
+    let total = files.len();
+    let checked = AtomicUsize::new(0);
+
+    let hashed: Vec<(String, FileInfo)> = files
+        .par_chunks(PROGRESS_CHUNK_SIZE)
+        .flat_map(|chunk| {
+            if progress.is_stopped() {
+                return Vec::new();
+            }
+            let hashed: Vec<(String, FileInfo)> = chunk
+                .iter()
+                .filter_map(|file| {
+                    let modified = system_time_to_secs(file.last_modified);
+
+                    if let Some(cache) = cache {
+                        if let Some(entry) = cache.lock().unwrap().get(&file.path) {
+                            if entry.size == file.size && entry.modified == modified && entry.algo == algo {
+                                return Some((entry.hash.clone(), file.clone()));
+                            }
+                        }
+                    }
+
+                    let hash = hash_file(&file.path, algo, limit).ok()?;
+
+                    if let Some(cache) = cache {
+                        cache.lock().unwrap().insert(
+                            file.path.clone(),
+                            CacheEntry { size: file.size, modified, algo, hash: hash.clone() },
+                        );
+                    }
+
+                    Some((hash, file.clone()))
+                })
+                .collect();
+
+            let done = checked.fetch_add(chunk.len(), Ordering::Relaxed) + chunk.len();
+            progress.report(stage, done, total);
+            hashed
+        })
+        .collect();
+
+    let mut groups: HashMap<String, Vec<FileInfo>> = HashMap::new();
+    for (hash, file) in hashed {
+        groups.entry(hash).or_default().push(file);
+    }
+    groups
+}
+
 /// Find files not accessed in X days
-pub fn find_old_files(root: &Path, days: u64) -> Vec<FileInfo> {
+pub fn find_old_files(root: &Path, days: u64, progress: &ProgressHandle, filter: &PathFilter) -> Vec<FileInfo> {
     let threshold = Duration::from_secs(days * 24 * 60 * 60);
     let now = SystemTime::now();
-    let mut old_files = Vec::new();
-    
-    for entry in WalkDir::new(root)
+
+    let entries: Vec<PathBuf> = WalkDir::new(root)
         .follow_links(false)
         .into_iter()
-        .filter_entry(|e| !should_exclude_path(e.path()))
-    {
-        if let Ok(entry) = entry {
-            if entry.file_type().is_file() {
-                if let Ok(metadata) = entry.metadata() {
-                    if let Ok(accessed) = metadata.accessed() {
-                        if let Ok(duration_since) = now.duration_since(accessed) {
-                            if duration_since >= threshold {
-                                if let Ok(modified) = metadata.modified() {
-                                    old_files.push(FileInfo {
-                                        path: entry.path().to_path_buf(),
-                                        size: metadata.len(),
-                                        last_accessed: accessed,
-                                        last_modified: modified,
-                                    });
-                                }
-                            }
-                        }
-                    }
-                }
+        .filter_entry(|e| !filter.is_excluded(e.path(), e.file_type().is_dir()))
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_file())
+        .map(|e| e.path().to_path_buf())
+        .collect();
+
+    let total = entries.len();
+    let checked = AtomicUsize::new(0);
+
+    entries
+        .par_chunks(PROGRESS_CHUNK_SIZE)
+        .flat_map(|chunk| {
+            if progress.is_stopped() {
+                return Vec::new();
             }
-        }
-    }
-    
-    old_files
+            let found: Vec<FileInfo> = chunk
+                .iter()
+                .filter_map(|path| {
+                    let metadata = fs::metadata(path).ok()?;
+                    let accessed = metadata.accessed().ok()?;
+                    let duration_since = now.duration_since(accessed).ok()?;
+                    if duration_since < threshold {
+                        return None;
+                    }
+                    let modified = metadata.modified().ok()?;
+                    Some(FileInfo {
+                        path: path.clone(),
+                        size: metadata.len(),
+                        last_accessed: accessed,
+                        last_modified: modified,
+                        dev_ino: dev_ino(&metadata),
+                    })
+                })
+                .collect();
+
+            let done = checked.fetch_add(chunk.len(), Ordering::Relaxed) + chunk.len();
+            progress.report(ScanStage::OldFiles, done, total);
+            found
+        })
+        .collect()
 }
 
 /// Calculate total size of a directory
@@ -229,7 +648,7 @@ fn calculate_dir_size(path: &Path) -> u64 {
 }
 
 /// Find common cache and build directories
-pub fn find_cache_directories(root: &Path) -> Vec<CacheInfo> {
+pub fn find_cache_directories(root: &Path, progress: &ProgressHandle, filter: &PathFilter) -> Vec<CacheInfo> {
     let cache_patterns = [
         ("node_modules", "npm/yarn"),
         ("target", "Rust/Cargo"),
@@ -251,24 +670,23 @@ pub fn find_cache_directories(root: &Path) -> Vec<CacheInfo> {
         .follow_links(false)
         .max_depth(6) // Limit depth for performance
         .into_iter()
-        .filter_entry(|e| !should_exclude_path(e.path()))
+        .filter_entry(|e| !filter.is_excluded(e.path(), e.file_type().is_dir()))
+        .flatten()
     {
-        if let Ok(entry) = entry {
-            if entry.file_type().is_dir() {
-                let path = entry.path();
-                let path_str = path.to_string_lossy();
-                
-                for (pattern, cache_type) in &cache_patterns {
-                    if path_str.ends_with(pattern) && !seen_paths.contains(path) {
-                        let size = calculate_dir_size(path);
-                        if size > 0 {
-                            cache_dirs.push(CacheInfo {
-                                path: path.to_path_buf(),
-                                cache_type: cache_type.to_string(),
-                                size,
-                            });
-                            seen_paths.insert(path.to_path_buf());
-                        }
+        if entry.file_type().is_dir() {
+            let path = entry.path();
+            let path_str = path.to_string_lossy();
+
+            for (pattern, cache_type) in &cache_patterns {
+                if path_str.ends_with(pattern) && !seen_paths.contains(path) {
+                    let size = calculate_dir_size(path);
+                    if size > 0 {
+                        cache_dirs.push(CacheInfo {
+                            path: path.to_path_buf(),
+                            cache_type: cache_type.to_string(),
+                            size,
+                        });
+                        seen_paths.insert(path.to_path_buf());
                     }
                 }
             }
@@ -276,31 +694,57 @@ pub fn find_cache_directories(root: &Path) -> Vec<CacheInfo> {
     }
     
     // Sort by size descending
-    cache_dirs.sort_by(|a, b| b.size.cmp(&a.size));
+    cache_dirs.sort_by_key(|c| std::cmp::Reverse(c.size));
+    progress.report(ScanStage::CacheDirs, cache_dirs.len(), cache_dirs.len());
     cache_dirs
 }
 
-/// Main entry point for disk analysis
-pub fn analyze_disk(root: &Path, config: AnalysisConfig) -> DiskAnalysis {
-    println!("Scanning for large files...");
-    let large_files = find_large_files(root, config.min_file_size_mb);
-    
-    println!("Scanning for duplicate files...");
-    let duplicate_groups = find_duplicates(root);
-    
-    println!("Scanning for old files...");
-    let old_files = find_old_files(root, config.old_file_days);
-    
-    println!("Scanning for cache directories...");
-    let cache_dirs = find_cache_directories(root);
+/// Main entry point for disk analysis. `progress` lets a caller (CLI or
+/// future GUI) render a progress bar and request early cancellation instead
+/// of the scan running silently to completion.
+pub fn analyze_disk(root: &Path, config: AnalysisConfig, progress: &ProgressHandle) -> DiskAnalysis {
+    let pool = rayon::ThreadPoolBuilder::new()
+        .num_threads(config.thread_count)
+        .build();
+
+    match pool {
+        Ok(pool) => pool.install(|| analyze_disk_inner(root, &config, progress)),
+        Err(_) => analyze_disk_inner(root, &config, progress),
+    }
+}
+
+fn analyze_disk_inner(root: &Path, config: &AnalysisConfig, progress: &ProgressHandle) -> DiskAnalysis {
+    let filter = PathFilter::new(config);
+
+    let large_files = find_large_files(root, config.min_file_size_mb, progress, &filter);
+
+    let duplicate_groups = if progress.is_stopped() {
+        Vec::new()
+    } else {
+        find_duplicates(root, config, progress, &filter)
+    };
+
+    let old_files = if progress.is_stopped() {
+        Vec::new()
+    } else {
+        find_old_files(root, config.old_file_days, progress, &filter)
+    };
+
+    let cache_dirs = if progress.is_stopped() {
+        Vec::new()
+    } else {
+        find_cache_directories(root, progress, &filter)
+    };
     
-    // Calculate total reclaimable space
+    // Calculate total reclaimable space. Hardlinked paths share one physical
+    // file, so they're collapsed before computing how many copies a group
+    // could actually give back.
     let duplicate_reclaimable: u64 = duplicate_groups
         .iter()
         .map(|group| {
-            // Can reclaim n-1 copies
-            if group.len() > 1 {
-                group[0].size * (group.len() as u64 - 1)
+            let physical_copies = physical_file_count(group) as u64;
+            if physical_copies > 1 {
+                group[0].size * (physical_copies - 1)
             } else {
                 0
             }
@@ -335,3 +779,198 @@ pub fn delete_file(path: &Path) -> io::Result<()> {
 pub fn delete_directory(path: &Path) -> io::Result<()> {
     fs::remove_dir_all(path)
 }
+
+/// Count the distinct physical files in a duplicate group, collapsing paths
+/// that share the same `(dev, ino)` (hardlinks to one another) down to one,
+/// since deleting a hardlink frees no space until the last link is removed.
+pub(crate) fn physical_file_count(group: &[FileInfo]) -> usize {
+    let mut seen = HashSet::new();
+    group
+        .iter()
+        .filter(|f| match f.dev_ino {
+            Some(id) => seen.insert(id),
+            None => true,
+        })
+        .count()
+}
+
+/// Build a sibling path to stash `path`'s content under while it's being
+/// replaced with a hard link, so a failed link can still be rolled back.
+#[cfg(unix)]
+fn temp_path_for(path: &Path) -> PathBuf {
+    let file_name = path.file_name().unwrap_or_default().to_string_lossy();
+    path.with_file_name(format!(".{}.dedupe-tmp", file_name))
+}
+
+/// Replace `path` with a hard link to `canonical`, restoring the original
+/// file if linking fails so `path` is never left missing. Fails fast if the
+/// two paths are on different devices, since hard links can't cross
+/// filesystems.
+#[cfg(unix)]
+fn make_hard_link(path: &Path, canonical: &Path) -> io::Result<()> {
+    let path_dev = dev_ino(&fs::metadata(path)?).map(|(dev, _)| dev);
+    let canonical_dev = dev_ino(&fs::metadata(canonical)?).map(|(dev, _)| dev);
+
+    if path_dev != canonical_dev {
+        return Err(io::Error::other(format!(
+            "{} and {} are on different devices, can't hard link across filesystems",
+            path.display(),
+            canonical.display()
+        )));
+    }
+
+    let temp = temp_path_for(path);
+    fs::rename(path, &temp)?;
+
+    match fs::hard_link(canonical, path) {
+        Ok(()) => {
+            let _ = fs::remove_file(&temp);
+            Ok(())
+        }
+        Err(e) => {
+            let _ = fs::rename(&temp, path);
+            Err(e)
+        }
+    }
+}
+
+/// Replace each duplicate copy in a group with a hard link to a single
+/// canonical file instead of deleting it, so every original path stays
+/// valid while the space is reclaimed. Paths already hardlinked to the
+/// canonical file are left untouched. A no-op on Windows.
+#[cfg(unix)]
+pub fn hardlink_duplicates(groups: &[Vec<FileInfo>], dry_run: bool) -> DeleteSummary {
+    let mut summary = DeleteSummary::default();
+
+    for group in groups {
+        if group.len() < 2 {
+            continue;
+        }
+
+        let canonical = &group[0];
+
+        for file in &group[1..] {
+            if file.dev_ino.is_some() && file.dev_ino == canonical.dev_ino {
+                continue;
+            }
+
+            if dry_run {
+                println!("Would hardlink: {} -> {}", file.path.display(), canonical.path.display());
+                summary.files_removed += 1;
+                summary.space_gained += file.size;
+                continue;
+            }
+
+            match make_hard_link(&file.path, &canonical.path) {
+                Ok(()) => {
+                    summary.files_removed += 1;
+                    summary.space_gained += file.size;
+                }
+                Err(e) => summary.failed.push((file.path.clone(), e)),
+            }
+        }
+    }
+
+    summary
+}
+
+#[cfg(not(unix))]
+pub fn hardlink_duplicates(_groups: &[Vec<FileInfo>], _dry_run: bool) -> DeleteSummary {
+    DeleteSummary::default()
+}
+
+/// How to resolve a group of duplicate files down to the copies worth
+/// keeping. `AllExcept*` keeps every file tied for the newest/oldest
+/// timestamp (there may be more than one); `KeepOne*` keeps exactly one copy
+/// even when several share that timestamp. `HardLink` keeps every path valid
+/// by linking every other copy to one canonical file instead of deleting it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DeleteMethod {
+    None,
+    AllExceptNewest,
+    AllExceptOldest,
+    KeepOneNewest,
+    KeepOneOldest,
+    HardLink,
+}
+
+/// Result of a `resolve_duplicates` pass.
+#[derive(Debug, Default)]
+pub struct DeleteSummary {
+    pub files_removed: usize,
+    pub space_gained: u64,
+    pub failed: Vec<(PathBuf, io::Error)>,
+}
+
+/// Apply `method` to each duplicate group, deleting the files it selects (or
+/// just reporting them, when `dry_run` is true) and returning a summary of
+/// what was removed and how much space that reclaimed.
+pub fn resolve_duplicates(groups: &[Vec<FileInfo>], method: DeleteMethod, dry_run: bool) -> DeleteSummary {
+    if method == DeleteMethod::HardLink {
+        return hardlink_duplicates(groups, dry_run);
+    }
+
+    let mut summary = DeleteSummary::default();
+
+    if method == DeleteMethod::None {
+        return summary;
+    }
+
+    for group in groups {
+        if group.len() < 2 {
+            continue;
+        }
+
+        // Hardlinked paths share one physical file, so the number of copies
+        // this group can actually give back is capped below `group.len()`.
+        let physical_copies = physical_file_count(group) as u64;
+        let mut deleted_in_group = 0u64;
+
+        for file in files_to_delete(group, method) {
+            if dry_run {
+                println!("Would delete: {}", file.path.display());
+                summary.files_removed += 1;
+                deleted_in_group += 1;
+                continue;
+            }
+
+            match delete_file(&file.path) {
+                Ok(_) => {
+                    summary.files_removed += 1;
+                    deleted_in_group += 1;
+                }
+                Err(e) => summary.failed.push((file.path.clone(), e)),
+            }
+        }
+
+        let freed_copies = deleted_in_group.min(physical_copies.saturating_sub(1));
+        summary.space_gained += freed_copies * group[0].size;
+    }
+
+    summary
+}
+
+/// Pick which files in a duplicate group `resolve_duplicates` should delete,
+/// based on `last_modified`.
+fn files_to_delete(group: &[FileInfo], method: DeleteMethod) -> Vec<&FileInfo> {
+    match method {
+        DeleteMethod::None => Vec::new(),
+        DeleteMethod::AllExceptNewest => {
+            let newest = group.iter().map(|f| f.last_modified).max().unwrap();
+            group.iter().filter(|f| f.last_modified != newest).collect()
+        }
+        DeleteMethod::AllExceptOldest => {
+            let oldest = group.iter().map(|f| f.last_modified).min().unwrap();
+            group.iter().filter(|f| f.last_modified != oldest).collect()
+        }
+        DeleteMethod::KeepOneNewest => {
+            let keep = group.iter().max_by_key(|f| f.last_modified).map(|f| f.path.clone());
+            group.iter().filter(|f| Some(&f.path) != keep.as_ref()).collect()
+        }
+        DeleteMethod::KeepOneOldest => {
+            let keep = group.iter().min_by_key(|f| f.last_modified).map(|f| f.path.clone());
+            group.iter().filter(|f| Some(&f.path) != keep.as_ref()).collect()
+        }
+        DeleteMethod::HardLink => Vec::new(),
+    }
+}
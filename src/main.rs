@@ -1,47 +1,333 @@
-use std::process::Command;
 use std::io::{self, Write};
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::sync::atomic::AtomicBool;
+use std::thread;
 
+use clap::Parser;
+
+mod docker;
 mod filesystem;
-use filesystem::{AnalysisConfig, analyze_disk, format_file_size, delete_file, delete_directory};
-
-#[derive(Debug)]
-struct ImageInfo {
-    id: String,
-    repository: String,
-    tag: String,
-    size: String,
+use filesystem::{
+    AnalysisConfig, DeleteMethod, HashAlgo, ProgressHandle, analyze_disk, format_file_size,
+    delete_file, delete_directory, physical_file_count, resolve_duplicates,
+};
+
+/// Clean up unused Docker resources and reclaim disk space.
+///
+/// With no flags, runs the interactive walkthrough. Passing any action flag
+/// (`--remove-dangling`, `--prune-stopped`, `--system-prune`,
+/// `--delete-duplicates`, `--older-than`) switches to non-interactive mode: only the
+/// requested actions run, with no prompts, which is what you want in a
+/// script or cron job.
+#[derive(Parser, Debug)]
+#[command(name = "docker-cleanup", version, about)]
+struct Cli {
+    /// Assume yes to any prompt (no-op in non-interactive mode, where there are none)
+    #[arg(short = 'y', long)]
+    yes: bool,
+
+    /// Preview what would be deleted without actually deleting anything
+    #[arg(long)]
+    dry_run: bool,
+
+    /// Directory to scan for the filesystem cleanup (defaults to the current directory)
+    #[arg(long, value_name = "DIR")]
+    scan_path: Option<PathBuf>,
+
+    /// Minimum size in MB for a file to count as "large"
+    #[arg(long, value_name = "MB")]
+    min_file_size_mb: Option<u64>,
+
+    /// Minimum days unaccessed for a file to count as "old"
+    #[arg(long, value_name = "DAYS")]
+    old_file_days: Option<u64>,
+
+    /// Hashing algorithm used to fingerprint file contents when looking for
+    /// duplicates: sha256, blake3, xxh3 (default), or crc32
+    #[arg(long, value_name = "ALGO")]
+    hash_algo: Option<String>,
+
+    /// Disable the on-disk hash cache, re-hashing every file on every run
+    #[arg(long)]
+    no_hash_cache: bool,
+
+    /// Only scan files with one of these extensions (comma-separated, e.g. jpg,png,mp4)
+    #[arg(long, value_delimiter = ',', value_name = "EXT")]
+    include_ext: Vec<String>,
+
+    /// Never scan files with one of these extensions (comma-separated)
+    #[arg(long, value_delimiter = ',', value_name = "EXT")]
+    exclude_ext: Vec<String>,
+
+    /// Prune these directories entirely during the scan (comma-separated, e.g. node_modules,.git)
+    #[arg(long, value_delimiter = ',', value_name = "DIR")]
+    exclude_dir: Vec<PathBuf>,
+
+    /// Remove dangling (untagged) Docker images
+    #[arg(long)]
+    remove_dangling: bool,
+
+    /// Remove stopped Docker containers
+    #[arg(long)]
+    prune_stopped: bool,
+
+    /// Run `docker system prune` equivalent (containers, networks, dangling images)
+    #[arg(long)]
+    system_prune: bool,
+
+    /// Delete duplicate files found by the filesystem scan (keeps the newest copy)
+    #[arg(long)]
+    delete_duplicates: bool,
+
+    /// Remove Docker images older than this many days
+    #[arg(long, value_name = "DAYS")]
+    older_than: Option<u64>,
+
+    /// When removing images by age, skip images backing a running container
+    #[arg(long)]
+    protect_running: bool,
+
+    /// When removing images by age, skip repositories matching these patterns (comma-separated substrings)
+    #[arg(long, value_delimiter = ',', value_name = "PATTERN")]
+    exclude_repo: Vec<String>,
+}
+
+impl Cli {
+    /// Whether any flag was passed that names a specific action to run,
+    /// which puts the tool in non-interactive mode.
+    fn has_action_flags(&self) -> bool {
+        self.remove_dangling
+            || self.prune_stopped
+            || self.system_prune
+            || self.delete_duplicates
+            || self.older_than.is_some()
+    }
+}
+
+#[tokio::main]
+async fn main() {
+    let cli = Cli::parse();
+
+    if cli.has_action_flags() {
+        run_noninteractive(&cli).await;
+    } else {
+        run_interactive(cli.dry_run, cli.yes).await;
+    }
+}
+
+/// Run exactly the actions named by `cli`'s flags, with no prompts. Suitable
+/// for scripts and cron jobs.
+async fn run_noninteractive(cli: &Cli) {
+    let client = match docker::connect() {
+        Ok(client) => client,
+        Err(e) => {
+            eprintln!("Error connecting to Docker daemon: {}", e);
+            return;
+        }
+    };
+
+    if cli.remove_dangling {
+        remove_dangling_images(&client, cli.dry_run).await;
+    }
+
+    if cli.prune_stopped {
+        prune_stopped_containers(&client, cli.dry_run).await;
+    }
+
+    if cli.system_prune {
+        system_prune(&client, cli.dry_run).await;
+    }
+
+    if cli.delete_duplicates {
+        let scan_path = cli.scan_path.clone().unwrap_or_else(|| PathBuf::from("."));
+        let mut config = AnalysisConfig::default();
+        if let Some(v) = cli.min_file_size_mb {
+            config.min_file_size_mb = v;
+        }
+        if let Some(v) = cli.old_file_days {
+            config.old_file_days = v;
+        }
+        config.allowed_extensions = cli.include_ext.clone();
+        config.excluded_extensions = cli.exclude_ext.clone();
+        config.excluded_directories = cli.exclude_dir.clone();
+        config.use_hash_cache = !cli.no_hash_cache;
+        if let Some(algo) = &cli.hash_algo {
+            match parse_hash_algo(algo) {
+                Some(parsed) => config.hash_algo = parsed,
+                None => eprintln!("Unknown hash algorithm '{}', using the default", algo),
+            }
+        }
+
+        let analysis = analyze_disk(&scan_path, config, &ProgressHandle::none());
+        remove_duplicates(&analysis, DeleteMethod::KeepOneNewest, cli.dry_run);
+    }
+
+    if let Some(days) = cli.older_than {
+        remove_old_images(&client, days, cli.protect_running, &cli.exclude_repo, false, cli.dry_run).await;
+    }
+}
+
+/// Remove dangling images, or report what would be removed if `dry_run`.
+async fn remove_dangling_images(client: &bollard::Docker, dry_run: bool) {
+    if dry_run {
+        match docker::count_dangling_images(client).await {
+            Ok(count) => println!("Would remove {} dangling image(s)", count),
+            Err(e) => eprintln!("Error listing dangling images: {}", e),
+        }
+        return;
+    }
+
+    match docker::remove_dangling_images(client).await {
+        Ok(report) => println!(
+            "Removed {} image(s), reclaimed {}",
+            report.images_deleted.map(|d| d.len()).unwrap_or(0),
+            format_file_size(report.space_reclaimed.unwrap_or(0).max(0) as u64)
+        ),
+        Err(e) => eprintln!("Error removing dangling images: {}", e),
+    }
 }
 
-#[derive(Debug)]
-struct ContainerInfo {
-    id: String,
-    name: String,
-    image: String,
-    status: String,
+/// Remove stopped containers, or report what would be removed if `dry_run`.
+async fn prune_stopped_containers(client: &bollard::Docker, dry_run: bool) {
+    if dry_run {
+        match docker::list_containers(client).await {
+            Ok(containers) => {
+                let stopped = containers
+                    .iter()
+                    .filter(|c| {
+                        let status = c.status.as_deref().unwrap_or("");
+                        status.starts_with("Exited") || status.starts_with("Created")
+                    })
+                    .count();
+                println!("Would remove {} stopped container(s)", stopped);
+            }
+            Err(e) => eprintln!("Error listing containers: {}", e),
+        }
+        return;
+    }
+
+    match docker::remove_stopped_containers(client).await {
+        Ok(report) => println!(
+            "Removed {} container(s), reclaimed {}",
+            report.containers_deleted.map(|d| d.len()).unwrap_or(0),
+            format_file_size(report.space_reclaimed.unwrap_or(0).max(0) as u64)
+        ),
+        Err(e) => eprintln!("Error removing stopped containers: {}", e),
+    }
 }
 
-fn main() {
+/// List and remove images older than `days`, optionally protecting images
+/// backing a running container and excluding matching repositories. Prints
+/// the candidate list and total size before doing anything; when `confirm`
+/// is set, asks before removing (skipped entirely in dry-run, where nothing
+/// is removed anyway).
+async fn remove_old_images(
+    client: &bollard::Docker,
+    days: u64,
+    protect_running: bool,
+    exclude_repos: &[String],
+    confirm: bool,
+    dry_run: bool,
+) {
+    let images = match docker::images_older_than(client, days, protect_running, exclude_repos).await {
+        Ok(images) => images,
+        Err(e) => {
+            eprintln!("Error listing images: {}", e);
+            return;
+        }
+    };
+
+    if images.is_empty() {
+        println!("No images older than {} days found", days);
+        return;
+    }
+
+    let total_size: i64 = images.iter().map(|i| i.size).sum();
+    println!(
+        "Found {} image(s) older than {} days, {} total:",
+        images.len(),
+        days,
+        format_file_size(total_size.max(0) as u64)
+    );
+    for img in &images {
+        let repo_tag = img.repo_tags.first().map(String::as_str).unwrap_or("<none>:<none>");
+        println!("  {} ({})", short_id(&img.id), repo_tag);
+    }
+
+    if dry_run {
+        println!("Would remove {} image(s), reclaiming {}", images.len(), format_file_size(total_size.max(0) as u64));
+        return;
+    }
+
+    if confirm && !prompt_yes_no("Remove these images?", false) {
+        return;
+    }
+
+    let results = docker::remove_images(client, &images).await;
+    let mut removed = 0;
+    for (id, result) in results {
+        match result {
+            Ok(()) => removed += 1,
+            Err(e) => eprintln!("Error removing {}: {}", short_id(&id), e),
+        }
+    }
+    println!("Removed {} image(s), reclaimed ~{}", removed, format_file_size(total_size.max(0) as u64));
+}
+
+/// Resolve duplicate groups by `method`, or report what would be removed if `dry_run`.
+fn remove_duplicates(analysis: &filesystem::DiskAnalysis, method: DeleteMethod, dry_run: bool) {
+    if analysis.duplicate_groups.is_empty() {
+        println!("No duplicate files found");
+        return;
+    }
+
+    let summary = resolve_duplicates(&analysis.duplicate_groups, method, dry_run);
+
+    for (path, e) in &summary.failed {
+        eprintln!("Error removing {}: {}", path.display(), e);
+    }
+
+    let verb = if dry_run { "Would remove" } else { "Removed" };
+    println!(
+        "{} {} duplicate files, {} {}",
+        verb,
+        summary.files_removed,
+        if dry_run { "would recover" } else { "recovered" },
+        format_file_size(summary.space_gained)
+    );
+}
+
+async fn run_interactive(dry_run: bool, assume_yes: bool) {
     println!("Docker Cleanup Tool\n");
-    
+
+    let client = match docker::connect() {
+        Ok(client) => client,
+        Err(e) => {
+            eprintln!("Error connecting to Docker daemon: {}", e);
+            return;
+        }
+    };
+
     // Check Docker images
-    match list_images() {
+    match docker::list_images(&client).await {
         Ok(images) => {
             println!("═══ Docker Images ═══");
             if images.is_empty() {
                 println!("No images found.\n");
             } else {
                 for (i, img) in images.iter().enumerate() {
-                    println!("{}. {} ({}:{})", i + 1, img.id, img.repository, img.tag);
-                    println!("   Size: {}\n", img.size);
+                    let repo_tag = img.repo_tags.first().map(String::as_str).unwrap_or("<none>:<none>");
+                    println!("{}. {} ({})", i + 1, short_id(&img.id), repo_tag);
+                    println!("   Size: {}\n", format_file_size(img.size.max(0) as u64));
                 }
-                
+
                 // Offer to remove dangling images
-                if let Ok(dangling) = count_dangling_images() {
+                if let Ok(dangling) = docker::count_dangling_images(&client).await {
                     if dangling > 0 {
                         println!("Found {} dangling image(s) (not tagged)", dangling);
-                        if prompt_yes_no("Remove dangling images?") {
-                            remove_dangling_images();
+                        if prompt_yes_no("Remove dangling images?", assume_yes) {
+                            remove_dangling_images(&client, dry_run).await;
                         }
                     }
                 }
@@ -49,9 +335,9 @@ fn main() {
         }
         Err(e) => eprintln!("Error listing images: {}", e),
     }
-    
+
     // Check Docker containers
-    match list_containers() {
+    match docker::list_containers(&client).await {
         Ok(containers) => {
             println!("\n═══ Docker Containers ═══");
             if containers.is_empty() {
@@ -59,180 +345,167 @@ fn main() {
             } else {
                 let mut stopped = Vec::new();
                 for (i, c) in containers.iter().enumerate() {
-                    println!("{}. {} ({})", i + 1, c.id, c.name);
-                    println!("   Image: {} | Status: {}\n", c.image, c.status);
-                    
-                    if c.status.starts_with("Exited") || c.status.starts_with("Created") {
+                    let name = c.names.as_ref().and_then(|n| n.first()).map(String::as_str).unwrap_or("<unnamed>");
+                    let image = c.image.as_deref().unwrap_or("<unknown>");
+                    let status = c.status.as_deref().unwrap_or("");
+                    println!("{}. {} ({})", i + 1, short_id(c.id.as_deref().unwrap_or("")), name.trim_start_matches('/'));
+                    println!("   Image: {} | Status: {}\n", image, status);
+
+                    if status.starts_with("Exited") || status.starts_with("Created") {
                         stopped.push(c);
                     }
                 }
-                
+
                 // Offer to remove stopped containers
                 if !stopped.is_empty() {
                     println!("Found {} stopped container(s)", stopped.len());
-                    if prompt_yes_no("Remove stopped containers?") {
-                        remove_stopped_containers();
+                    if prompt_yes_no("Remove stopped containers?", assume_yes) {
+                        prune_stopped_containers(&client, dry_run).await;
                     }
                 }
             }
         }
         Err(e) => eprintln!("Error listing containers: {}", e),
     }
-    
+
     // Show disk usage
     println!("\n═══ Docker Disk Usage ═══");
-    show_disk_usage();
-    
+    show_disk_usage(&client).await;
+
     // Offer full cleanup
     println!("\n═══ Additional Cleanup Options ═══");
-    if prompt_yes_no("Run full system prune (removes unused data)?") {
-        system_prune();
+    if prompt_yes_no("Run full system prune (removes unused data)?", assume_yes) {
+        system_prune(&client, dry_run).await;
     }
-    
+
+    print!("Remove images older than how many days? (or Enter to skip): ");
+    io::stdout().flush().unwrap();
+    let mut input = String::new();
+    io::stdin().read_line(&mut input).unwrap();
+    if let Ok(days) = input.trim().parse::<u64>() {
+        remove_old_images(&client, days, true, &[], !assume_yes, dry_run).await;
+    }
+
     // Filesystem cleanup section
     println!("\n═══ Filesystem Cleanup ═══");
-    if prompt_yes_no("Run filesystem analysis?") {
-        run_filesystem_cleanup();
+    if prompt_yes_no("Run filesystem analysis?", assume_yes) {
+        run_filesystem_cleanup(dry_run, assume_yes);
     }
 }
 
-fn list_images() -> Result<Vec<ImageInfo>, String> {
-    let output = Command::new("docker")
-        .args(&["images", "--format", "{{.ID}}|{{.Repository}}|{{.Tag}}|{{.Size}}"])
-        .output()
-        .map_err(|e| format!("Failed to execute docker command: {}", e))?;
-    
-    if !output.status.success() {
-        return Err("Docker command failed".to_string());
-    }
-    
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    let images = stdout
-        .lines()
-        .filter_map(|line| {
-            let parts: Vec<&str> = line.split('|').collect();
-            if parts.len() == 4 {
-                Some(ImageInfo {
-                    id: parts[0].to_string(),
-                    repository: parts[1].to_string(),
-                    tag: parts[2].to_string(),
-                    size: parts[3].to_string(),
-                })
-            } else {
-                None
-            }
-        })
-        .collect();
-    
-    Ok(images)
+/// Docker's own CLI shows only the first 12 hex characters of an image or
+/// container ID; bollard gives us the full `sha256:...`/64-char form.
+fn short_id(id: &str) -> &str {
+    let id = id.strip_prefix("sha256:").unwrap_or(id);
+    &id[..id.len().min(12)]
 }
 
-fn list_containers() -> Result<Vec<ContainerInfo>, String> {
-    let output = Command::new("docker")
-        .args(&["ps", "-a", "--format", "{{.ID}}|{{.Names}}|{{.Image}}|{{.Status}}"])
-        .output()
-        .map_err(|e| format!("Failed to execute docker command: {}", e))?;
-    
-    if !output.status.success() {
-        return Err("Docker command failed".to_string());
-    }
-    
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    let containers = stdout
-        .lines()
-        .filter_map(|line| {
-            let parts: Vec<&str> = line.split('|').collect();
-            if parts.len() == 4 {
-                Some(ContainerInfo {
-                    id: parts[0].to_string(),
-                    name: parts[1].to_string(),
-                    image: parts[2].to_string(),
-                    status: parts[3].to_string(),
-                })
-            } else {
-                None
-            }
-        })
-        .collect();
-    
-    Ok(containers)
-}
+async fn show_disk_usage(client: &bollard::Docker) {
+    match docker::disk_usage(client).await {
+        Ok(usage) => {
+            let images = usage.images.unwrap_or_default();
+            let containers = usage.containers.unwrap_or_default();
+            let volumes = usage.volumes.unwrap_or_default();
 
-fn count_dangling_images() -> Result<usize, String> {
-    let output = Command::new("docker")
-        .args(&["images", "-f", "dangling=true", "-q"])
-        .output()
-        .map_err(|e| format!("Failed to execute docker command: {}", e))?;
-    
-    Ok(String::from_utf8_lossy(&output.stdout).lines().count())
-}
+            let images_size: i64 = images.iter().map(|i| i.size).sum();
+            let containers_size: i64 = containers.iter().filter_map(|c| c.size_rw).sum();
+            let volumes_size: i64 = volumes.iter().filter_map(|v| v.usage_data.as_ref().map(|u| u.size)).sum();
 
-fn remove_dangling_images() {
-    println!("Removing dangling images...");
-    let output = Command::new("docker")
-        .args(&["image", "prune", "-f"])
-        .output();
-    
-    match output {
-        Ok(o) => {
-            println!("{}", String::from_utf8_lossy(&o.stdout));
+            println!("Images:     {} ({} total)", format_file_size(images_size.max(0) as u64), images.len());
+            println!("Containers: {} ({} total)", format_file_size(containers_size.max(0) as u64), containers.len());
+            println!("Volumes:    {} ({} total)", format_file_size(volumes_size.max(0) as u64), volumes.len());
         }
-        Err(e) => eprintln!("Error: {}", e),
+        Err(e) => eprintln!("Error fetching disk usage: {}", e),
     }
 }
 
-fn remove_stopped_containers() {
-    println!("Removing stopped containers...");
-    let output = Command::new("docker")
-        .args(&["container", "prune", "-f"])
-        .output();
-    
-    match output {
-        Ok(o) => {
-            println!("{}", String::from_utf8_lossy(&o.stdout));
-        }
-        Err(e) => eprintln!("Error: {}", e),
+async fn system_prune(client: &bollard::Docker, dry_run: bool) {
+    if dry_run {
+        println!("Would run system prune (containers, networks, dangling images)");
+        return;
     }
-}
 
-fn show_disk_usage() {
-    let output = Command::new("docker")
-        .args(&["system", "df"])
-        .output();
-    
-    match output {
-        Ok(o) => {
-            println!("{}", String::from_utf8_lossy(&o.stdout));
+    println!("Running system prune...");
+    match docker::system_prune(client).await {
+        Ok(report) => {
+            let containers_removed = report.containers.and_then(|r| r.containers_deleted).map(|d| d.len()).unwrap_or(0);
+            let networks_removed = report.networks.and_then(|r| r.networks_deleted).map(|d| d.len()).unwrap_or(0);
+            let images_removed = report.images.and_then(|r| r.images_deleted).map(|d| d.len()).unwrap_or(0);
+            println!(
+                "Removed {} container(s), {} network(s), {} image(s)",
+                containers_removed, networks_removed, images_removed
+            );
         }
-        Err(e) => eprintln!("Error: {}", e),
+        Err(e) => eprintln!("Error running system prune: {}", e),
     }
 }
 
-fn system_prune() {
-    println!("Running system prune...");
-    let output = Command::new("docker")
-        .args(&["system", "prune", "-f"])
-        .output();
-    
-    match output {
-        Ok(o) => {
-            println!("{}", String::from_utf8_lossy(&o.stdout));
-        }
-        Err(e) => eprintln!("Error: {}", e),
+/// Parse a `--hash-algo`/prompt value into a `HashAlgo`. Returns `None` for
+/// anything unrecognized so the caller can fall back to the default.
+fn parse_hash_algo(value: &str) -> Option<HashAlgo> {
+    match value.trim().to_lowercase().as_str() {
+        "sha256" => Some(HashAlgo::Sha256),
+        "blake3" => Some(HashAlgo::Blake3),
+        "xxh3" => Some(HashAlgo::Xxh3),
+        "crc32" => Some(HashAlgo::Crc32),
+        _ => None,
     }
 }
 
-fn prompt_yes_no(question: &str) -> bool {
+/// Parse a comma-separated prompt answer into a trimmed, non-empty list.
+fn parse_csv_list(input: &str) -> Vec<String> {
+    input
+        .trim()
+        .split(',')
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(String::from)
+        .collect()
+}
+
+/// Ask `question`, unless `assume_yes` is set (`--yes`/`-y`), in which case
+/// the prompt is skipped and answered yes automatically.
+fn prompt_yes_no(question: &str, assume_yes: bool) -> bool {
+    if assume_yes {
+        println!("{} (y/N): y (assumed by --yes)", question);
+        return true;
+    }
+
     print!("{} (y/N): ", question);
     io::stdout().flush().unwrap();
-    
+
     let mut input = String::new();
     io::stdin().read_line(&mut input).unwrap();
-    
+
     matches!(input.trim().to_lowercase().as_str(), "y" | "yes")
 }
 
-fn run_filesystem_cleanup() {
+/// Ask which copies to keep in each duplicate group. Returns `None` if the
+/// user backs out without picking anything.
+fn prompt_delete_method() -> Option<DeleteMethod> {
+    println!("Which copies should be kept?");
+    println!("1. Keep all copies tied for newest (delete the rest)");
+    println!("2. Keep all copies tied for oldest (delete the rest)");
+    println!("3. Keep exactly one copy, the newest");
+    println!("4. Keep exactly one copy, the oldest");
+    println!("5. Keep every path, hard-link duplicates to one canonical copy");
+    print!("Choice (or Enter to cancel): ");
+    io::stdout().flush().unwrap();
+
+    let mut input = String::new();
+    io::stdin().read_line(&mut input).unwrap();
+
+    match input.trim() {
+        "1" => Some(DeleteMethod::AllExceptNewest),
+        "2" => Some(DeleteMethod::AllExceptOldest),
+        "3" => Some(DeleteMethod::KeepOneNewest),
+        "4" => Some(DeleteMethod::KeepOneOldest),
+        "5" => Some(DeleteMethod::HardLink),
+        _ => None,
+    }
+}
+
+fn run_filesystem_cleanup(dry_run: bool, assume_yes: bool) {
     // Determine scan path
     print!("Enter directory to scan (or press Enter for current directory): ");
     io::stdout().flush().unwrap();
@@ -251,12 +524,75 @@ fn run_filesystem_cleanup() {
         eprintln!("Error: Path does not exist");
         return;
     }
-    
+
+    let mut config = AnalysisConfig::default();
+
+    print!("File extensions to include, comma-separated (or Enter for all): ");
+    io::stdout().flush().unwrap();
+    let mut input = String::new();
+    io::stdin().read_line(&mut input).unwrap();
+    config.allowed_extensions = parse_csv_list(&input);
+
+    print!("File extensions to exclude, comma-separated (or Enter for none): ");
+    io::stdout().flush().unwrap();
+    let mut input = String::new();
+    io::stdin().read_line(&mut input).unwrap();
+    config.excluded_extensions = parse_csv_list(&input);
+
+    print!("Directories to exclude, comma-separated (or Enter for none, e.g. node_modules,.git): ");
+    io::stdout().flush().unwrap();
+    let mut input = String::new();
+    io::stdin().read_line(&mut input).unwrap();
+    config.excluded_directories = parse_csv_list(&input).into_iter().map(PathBuf::from).collect();
+
+    print!("Hash algorithm for duplicate detection (sha256/blake3/xxh3/crc32, or Enter for default): ");
+    io::stdout().flush().unwrap();
+    let mut input = String::new();
+    io::stdin().read_line(&mut input).unwrap();
+    let input = input.trim();
+    if !input.is_empty() {
+        match parse_hash_algo(input) {
+            Some(algo) => config.hash_algo = algo,
+            None => eprintln!("Unknown hash algorithm '{}', using the default", input),
+        }
+    }
+
+    if prompt_yes_no("Disable the on-disk hash cache (re-hash every file on every run)?", assume_yes) {
+        config.use_hash_cache = false;
+    }
+
     println!("\n═══ Filesystem Analysis ═══");
     println!("Scanning: {}\n", scan_path.display());
-    
-    let config = AnalysisConfig::default();
-    let analysis = analyze_disk(&scan_path, config.clone());
+
+    let (progress_tx, progress_rx) = crossbeam_channel::unbounded();
+    let stop = Arc::new(AtomicBool::new(false));
+
+    {
+        let stop = stop.clone();
+        let _ = ctrlc::set_handler(move || {
+            println!("\nCancelling scan...");
+            stop.store(true, std::sync::atomic::Ordering::Relaxed);
+        });
+    }
+
+    let progress = ProgressHandle::new(progress_tx, stop);
+
+    let progress_printer = thread::spawn(move || {
+        for update in progress_rx {
+            print!(
+                "\rScanning {}: {}/{} files checked",
+                update.stage.label(),
+                update.files_checked,
+                update.files_to_check
+            );
+            let _ = io::stdout().flush();
+        }
+        println!();
+    });
+
+    let analysis = analyze_disk(&scan_path, config.clone(), &progress);
+    drop(progress);
+    let _ = progress_printer.join();
     
     // Display large files
     println!("\n═══ Top {} Largest Files ═══", config.max_large_files);
@@ -277,11 +613,14 @@ fn run_filesystem_cleanup() {
     if analysis.duplicate_groups.is_empty() {
         println!("No duplicate files found");
     } else {
+        // Hardlinked paths share one physical file, so they're collapsed
+        // before counting how many copies a group could actually give back.
         let duplicate_reclaimable: u64 = analysis.duplicate_groups
             .iter()
             .map(|group| {
-                if group.len() > 1 {
-                    group[0].size * (group.len() as u64 - 1)
+                let physical_copies = physical_file_count(group) as u64;
+                if physical_copies > 1 {
+                    group[0].size * (physical_copies - 1)
                 } else {
                     0
                 }
@@ -325,7 +664,7 @@ fn run_filesystem_cleanup() {
         
         for cache in &analysis.cache_dirs {
             cache_by_type.entry(cache.cache_type.clone())
-                .or_insert_with(Vec::new)
+                .or_default()
                 .push(cache);
         }
         
@@ -360,146 +699,125 @@ fn run_filesystem_cleanup() {
     println!("\n═══ Cleanup Options ═══");
     
     // Option 1: Remove duplicate files
-    if !analysis.duplicate_groups.is_empty() {
-        if prompt_yes_no("Remove duplicate files (keep one copy)?") {
-            let mut removed_count = 0;
-            let mut removed_size = 0u64;
-            
-            for group in &analysis.duplicate_groups {
-                if group.len() > 1 {
-                    // Keep the first file, remove the rest
-                    for file in group.iter().skip(1) {
-                        match delete_file(&file.path) {
-                            Ok(_) => {
-                                println!("Removed: {}", file.path.display());
-                                removed_count += 1;
-                                removed_size += file.size;
-                            }
-                            Err(e) => {
-                                eprintln!("Error removing {}: {}", file.path.display(), e);
-                            }
-                        }
-                    }
-                }
-            }
-            
-            println!("Removed {} duplicate files, recovered {}", 
-                removed_count, 
-                format_file_size(removed_size)
-            );
+    if !analysis.duplicate_groups.is_empty() && prompt_yes_no("Remove duplicate files?", assume_yes) {
+        if let Some(method) = prompt_delete_method() {
+            remove_duplicates(&analysis, method, dry_run);
         }
     }
-    
+
     // Option 2: Clear cache directories
-    if !analysis.cache_dirs.is_empty() {
-        if prompt_yes_no("Clear cache directories?") {
-            println!("Select cache types to clear:");
-            
-            let mut cache_by_type: std::collections::HashMap<String, Vec<&filesystem::CacheInfo>> = 
-                std::collections::HashMap::new();
-            
-            for cache in &analysis.cache_dirs {
-                cache_by_type.entry(cache.cache_type.clone())
-                    .or_insert_with(Vec::new)
-                    .push(cache);
-            }
-            
-            for (i, (cache_type, caches)) in cache_by_type.iter().enumerate() {
-                let type_total: u64 = caches.iter().map(|c| c.size).sum();
-                println!("{}. {} ({}, {} directories)", 
-                    i + 1,
-                    cache_type, 
-                    format_file_size(type_total),
-                    caches.len()
-                );
-            }
-            
-            print!("Enter numbers to clear (comma-separated, or 'all'): ");
-            io::stdout().flush().unwrap();
-            
-            let mut input = String::new();
-            io::stdin().read_line(&mut input).unwrap();
-            let input = input.trim();
+    if !analysis.cache_dirs.is_empty() && prompt_yes_no("Clear cache directories?", assume_yes) {
+        println!("Select cache types to clear:");
+        
+        let mut cache_by_type: std::collections::HashMap<String, Vec<&filesystem::CacheInfo>> = 
+            std::collections::HashMap::new();
+        
+        for cache in &analysis.cache_dirs {
+            cache_by_type.entry(cache.cache_type.clone())
+                .or_default()
+                .push(cache);
+        }
+        
+        for (i, (cache_type, caches)) in cache_by_type.iter().enumerate() {
+            let type_total: u64 = caches.iter().map(|c| c.size).sum();
+            println!("{}. {} ({}, {} directories)", 
+                i + 1,
+                cache_type, 
+                format_file_size(type_total),
+                caches.len()
+            );
+        }
+        
+        print!("Enter numbers to clear (comma-separated, or 'all'): ");
+        io::stdout().flush().unwrap();
+        
+        let mut input = String::new();
+        io::stdin().read_line(&mut input).unwrap();
+        let input = input.trim();
+        
+        if !input.is_empty() {
+            let mut removed_size = 0u64;
+            let cache_types: Vec<_> = cache_by_type.keys().cloned().collect();
             
-            if !input.is_empty() {
-                let mut removed_size = 0u64;
-                let cache_types: Vec<_> = cache_by_type.keys().cloned().collect();
-                
-                if input.to_lowercase() == "all" {
-                    for cache in &analysis.cache_dirs {
-                        match delete_directory(&cache.path) {
-                            Ok(_) => {
-                                println!("Removed: {}", cache.path.display());
-                                removed_size += cache.size;
-                            }
-                            Err(e) => {
-                                eprintln!("Error removing {}: {}", cache.path.display(), e);
-                            }
-                        }
+            let remove_cache = |cache: &filesystem::CacheInfo, removed_size: &mut u64| {
+                if dry_run {
+                    println!("Would delete: {}", cache.path.display());
+                    *removed_size += cache.size;
+                    return;
+                }
+                match delete_directory(&cache.path) {
+                    Ok(_) => {
+                        println!("Removed: {}", cache.path.display());
+                        *removed_size += cache.size;
                     }
-                } else {
-                    let selections: Vec<usize> = input
-                        .split(',')
-                        .filter_map(|s| s.trim().parse::<usize>().ok())
-                        .collect();
-                    
-                    for idx in selections {
-                        if idx > 0 && idx <= cache_types.len() {
-                            let cache_type = &cache_types[idx - 1];
-                            if let Some(caches) = cache_by_type.get(cache_type) {
-                                for cache in caches {
-                                    match delete_directory(&cache.path) {
-                                        Ok(_) => {
-                                            println!("Removed: {}", cache.path.display());
-                                            removed_size += cache.size;
-                                        }
-                                        Err(e) => {
-                                            eprintln!("Error removing {}: {}", cache.path.display(), e);
-                                        }
-                                    }
-                                }
+                    Err(e) => {
+                        eprintln!("Error removing {}: {}", cache.path.display(), e);
+                    }
+                }
+            };
+
+            if input.to_lowercase() == "all" {
+                for cache in &analysis.cache_dirs {
+                    remove_cache(cache, &mut removed_size);
+                }
+            } else {
+                let selections: Vec<usize> = input
+                    .split(',')
+                    .filter_map(|s| s.trim().parse::<usize>().ok())
+                    .collect();
+
+                for idx in selections {
+                    if idx > 0 && idx <= cache_types.len() {
+                        let cache_type = &cache_types[idx - 1];
+                        if let Some(caches) = cache_by_type.get(cache_type) {
+                            for cache in caches {
+                                remove_cache(cache, &mut removed_size);
                             }
                         }
                     }
                 }
-                
-                println!("Recovered {}", format_file_size(removed_size));
             }
+
+            let verb = if dry_run { "Would recover" } else { "Recovered" };
+            println!("{} {}", verb, format_file_size(removed_size));
         }
     }
-    
+
     // Option 3: Remove old files
-    if !analysis.old_files.is_empty() {
-        if prompt_yes_no(&format!("Remove files not accessed in {}+ days?", config.old_file_days)) {
-            print!("Are you sure? This will delete {} files (y/N): ", analysis.old_files.len());
-            io::stdout().flush().unwrap();
-            
-            let mut input = String::new();
-            io::stdin().read_line(&mut input).unwrap();
-            
-            if matches!(input.trim().to_lowercase().as_str(), "y" | "yes") {
-                let mut removed_count = 0;
-                let mut removed_size = 0u64;
-                
-                for file in &analysis.old_files {
-                    match delete_file(&file.path) {
-                        Ok(_) => {
-                            removed_count += 1;
-                            removed_size += file.size;
-                        }
-                        Err(e) => {
-                            eprintln!("Error removing {}: {}", file.path.display(), e);
-                        }
+    if !analysis.old_files.is_empty()
+        && prompt_yes_no(&format!("Remove files not accessed in {}+ days?", config.old_file_days), assume_yes)
+    {
+        if prompt_yes_no(&format!("Are you sure? This will delete {} files", analysis.old_files.len()), assume_yes) {
+            let mut removed_count = 0;
+            let mut removed_size = 0u64;
+
+            for file in &analysis.old_files {
+                if dry_run {
+                    println!("Would delete: {}", file.path.display());
+                    removed_count += 1;
+                    removed_size += file.size;
+                    continue;
+                }
+                match delete_file(&file.path) {
+                    Ok(_) => {
+                        removed_count += 1;
+                        removed_size += file.size;
+                    }
+                    Err(e) => {
+                        eprintln!("Error removing {}: {}", file.path.display(), e);
                     }
                 }
-                
-                println!("Removed {} old files, recovered {}", 
-                    removed_count, 
-                    format_file_size(removed_size)
-                );
             }
+
+            let verb = if dry_run { "Would remove" } else { "Removed" };
+            println!("{} {} old files, {} {}",
+                verb,
+                removed_count,
+                if dry_run { "would recover" } else { "recovered" },
+                format_file_size(removed_size)
+            );
         }
     }
-    
+
     println!("\nFilesystem cleanup complete!");
 }